@@ -0,0 +1,38 @@
+use opencv::videoio::{self, VideoCapture};
+use std::env;
+
+/// Where to read frames from: a local webcam index or a network stream
+/// URL, selected via the `SOURCE` env var (`webcam:0`, `rtsp://host/stream`).
+#[derive(Clone, Debug)]
+pub enum CaptureSource {
+    Webcam(i32),
+    Network(String),
+}
+
+impl CaptureSource {
+    pub fn from_env() -> Self {
+        match env::var("SOURCE") {
+            Ok(value) => Self::parse(&value),
+            Err(_) => CaptureSource::Webcam(0),
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        if let Some(index) = value.strip_prefix("webcam:") {
+            if let Ok(index) = index.parse() {
+                return CaptureSource::Webcam(index);
+            }
+        }
+        CaptureSource::Network(value.to_string())
+    }
+
+    /// Open the underlying `VideoCapture`. Network sources go through
+    /// OpenCV's FFmpeg backend so RTSP/HTTP streams behave the same way
+    /// the USB path does.
+    pub fn open(&self) -> opencv::Result<VideoCapture> {
+        match self {
+            CaptureSource::Webcam(index) => VideoCapture::new(*index, videoio::CAP_ANY),
+            CaptureSource::Network(url) => VideoCapture::from_file(url, videoio::CAP_FFMPEG),
+        }
+    }
+}
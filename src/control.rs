@@ -0,0 +1,163 @@
+use std::env;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+use zmq::Context;
+
+/// Live capture settings. The capture loop re-reads this through its
+/// `Arc<Mutex<_>>` on every iteration, so changes made over the control
+/// socket take effect without restarting the process.
+#[derive(Clone, Debug)]
+pub struct CaptureConfig {
+    pub fps: u32,
+    pub width: i32,
+    pub height: i32,
+    pub jpeg_quality: i32,
+    pub paused: bool,
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        CaptureConfig {
+            fps: 30,
+            width: 640,
+            height: 480,
+            jpeg_quality: 90,
+            paused: false,
+        }
+    }
+}
+
+/// Running counters surfaced through `GetStats`.
+#[derive(Default)]
+pub struct CaptureStats {
+    pub frames_captured: AtomicU64,
+    pub frames_dropped: AtomicU64,
+    pub camera_reconnects: AtomicU32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "command")]
+enum ControlRequest {
+    Ping,
+    GetStats,
+    SetFps { fps: u32 },
+    SetResolution { width: i32, height: i32 },
+    SetJpegQuality { quality: i32 },
+    Pause,
+    Resume,
+    Shutdown,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "status")]
+enum ControlResponse {
+    Ok,
+    Stats {
+        frames_captured: u64,
+        frames_dropped: u64,
+        fps: u32,
+        camera_reconnects: u32,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// Spawn the REQ/REP control-plane thread and return immediately; the
+/// socket runs on its own thread for the lifetime of the process.
+pub fn spawn_control_thread(
+    context: &Context,
+    config: Arc<Mutex<CaptureConfig>>,
+    stats: Arc<CaptureStats>,
+) {
+    let context = context.clone();
+    thread::spawn(move || control_loop(&context, &config, &stats));
+}
+
+fn control_loop(context: &Context, config: &Arc<Mutex<CaptureConfig>>, stats: &Arc<CaptureStats>) {
+    let address = env::var("CONTROL_ADDRESS").unwrap_or_else(|_| "tcp://*:5556".to_string());
+
+    let socket = match context.socket(zmq::REP) {
+        Ok(socket) => socket,
+        Err(e) => {
+            eprintln!("Failed to create control socket: {:?}", e);
+            return;
+        }
+    };
+    if let Err(e) = socket.bind(&address) {
+        eprintln!("Failed to bind control socket to {}: {:?}", address, e);
+        return;
+    }
+
+    loop {
+        let request = match socket.recv_string(0) {
+            Ok(Ok(request)) => request,
+            _ => {
+                eprintln!("Failed to receive control request");
+                continue;
+            }
+        };
+
+        let response = handle_request(&request, config, stats);
+        let payload = serde_json::to_string(&response).unwrap_or_else(|_| {
+            "{\"status\":\"Error\",\"message\":\"failed to encode response\"}".to_string()
+        });
+        if let Err(e) = socket.send(&payload, 0) {
+            eprintln!("Failed to send control response: {:?}", e);
+        }
+    }
+}
+
+fn handle_request(
+    raw: &str,
+    config: &Arc<Mutex<CaptureConfig>>,
+    stats: &Arc<CaptureStats>,
+) -> ControlResponse {
+    let request: ControlRequest = match serde_json::from_str(raw) {
+        Ok(request) => request,
+        Err(e) => {
+            return ControlResponse::Error {
+                message: format!("invalid request: {}", e),
+            }
+        }
+    };
+
+    match request {
+        ControlRequest::Ping => ControlResponse::Ok,
+        ControlRequest::GetStats => {
+            let fps = config.lock().unwrap().fps;
+            ControlResponse::Stats {
+                frames_captured: stats.frames_captured.load(Ordering::Relaxed),
+                frames_dropped: stats.frames_dropped.load(Ordering::Relaxed),
+                fps,
+                camera_reconnects: stats.camera_reconnects.load(Ordering::Relaxed),
+            }
+        }
+        ControlRequest::SetFps { fps } => {
+            config.lock().unwrap().fps = fps;
+            ControlResponse::Ok
+        }
+        ControlRequest::SetResolution { width, height } => {
+            let mut cfg = config.lock().unwrap();
+            cfg.width = width;
+            cfg.height = height;
+            ControlResponse::Ok
+        }
+        ControlRequest::SetJpegQuality { quality } => {
+            config.lock().unwrap().jpeg_quality = quality;
+            ControlResponse::Ok
+        }
+        ControlRequest::Pause => {
+            config.lock().unwrap().paused = true;
+            ControlResponse::Ok
+        }
+        ControlRequest::Resume => {
+            config.lock().unwrap().paused = false;
+            ControlResponse::Ok
+        }
+        ControlRequest::Shutdown => std::process::exit(0),
+    }
+}
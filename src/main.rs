@@ -4,8 +4,20 @@ use opencv::core::Mat;
 use zmq::{Context, Socket};
 use std::time::Duration;
 use std::process::exit;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
 use nix::unistd::{fork, ForkResult, setsid};
 
+mod capture_source;
+mod control;
+mod motion;
+mod recording;
+
+use capture_source::CaptureSource;
+use control::{CaptureConfig, CaptureStats};
+use motion::MotionGate;
+use recording::{Recorder, RecordingConfig, RealClock};
+
 fn main() -> opencv::Result<()> {
     // Daemonize the process
     daemonize();
@@ -40,32 +52,88 @@ fn run_capture_loop() -> opencv::Result<()> {
     let context = Context::new();
     let socket = setup_socket(&context, "tcp://*:5555");
 
-    // Initialize the webcam capture
-    let mut cam = setup_camera();
+    // Shared, live-reconfigurable settings and counters, driven by the
+    // control socket so FPS/resolution/quality can change without a restart.
+    let config = Arc::new(Mutex::new(CaptureConfig::default()));
+    let stats = Arc::new(CaptureStats::default());
+    control::spawn_control_thread(&context, Arc::clone(&config), Arc::clone(&stats));
+
+    // Initialize the capture source (local webcam or network stream)
+    let source = CaptureSource::from_env();
+    let mut cam = setup_camera(&source, &config.lock().unwrap());
+
+    // Skip forwarding frames that look the same as the last one.
+    let mut motion_gate = MotionGate::from_env();
+
+    // Persist captured frames to disk as rotating segments, if enabled.
+    let mut recorder = Recorder::new(RecordingConfig::from_env(), RealClock);
+
+    // Resolution currently applied to the open `VideoCapture`, so a live
+    // `SetResolution` can be detected and re-applied without a restart.
+    let mut applied_width = config.lock().unwrap().width;
+    let mut applied_height = config.lock().unwrap().height;
 
     loop {
+        let cfg = config.lock().unwrap().clone();
+
+        if cfg.paused {
+            std::thread::sleep(Duration::from_millis(100));
+            continue;
+        }
+
+        if cfg.width != applied_width || cfg.height != applied_height {
+            cam.set(videoio::CAP_PROP_FRAME_WIDTH, cfg.width as f64).ok();
+            cam.set(videoio::CAP_PROP_FRAME_HEIGHT, cfg.height as f64).ok();
+            applied_width = cfg.width;
+            applied_height = cfg.height;
+        }
+
         // Attempt to capture a frame
         let mut frame = Mat::default();
         match cam.read(&mut frame) {
             Ok(_) if !frame.empty() => {
-                // Encode the frame as bytes (e.g., as a JPEG) before sending
-                let mut encoded = opencv::core::Vector::<u8>::new();
-                if let Ok(_) = opencv::imgcodecs::imencode(".jpg", &frame, &mut encoded, &Default::default()) {
-                    // Attempt to send the frame
-                    if let Err(_) = try_send(&socket, &encoded) {
-                        eprintln!("Failed to send frame, will retry later");
+                let frame_size = frame
+                    .size()
+                    .unwrap_or_else(|_| opencv::core::Size::new(cfg.width, cfg.height));
+                if let Err(e) = recorder.record_frame(&frame, frame_size, cfg.fps as f64) {
+                    eprintln!("Failed to record frame: {:?}", e);
+                }
+
+                if motion_gate.should_emit(&frame).unwrap_or(true) {
+                    // Encode the frame as bytes (e.g., as a JPEG) before sending
+                    let mut params = opencv::core::Vector::<i32>::new();
+                    params.push(opencv::imgcodecs::IMWRITE_JPEG_QUALITY);
+                    params.push(cfg.jpeg_quality);
+
+                    let mut encoded = opencv::core::Vector::<u8>::new();
+                    if let Ok(_) = opencv::imgcodecs::imencode(".jpg", &frame, &mut encoded, &params) {
+                        // Attempt to send the frame
+                        match try_send(&socket, &encoded) {
+                            Ok(_) => {
+                                stats.frames_captured.fetch_add(1, Ordering::Relaxed);
+                            }
+                            Err(_) => {
+                                eprintln!("Failed to send frame, dropping it");
+                                stats.frames_dropped.fetch_add(1, Ordering::Relaxed);
+                            }
+                        }
                     }
                 }
             }
             Ok(_) => eprintln!("Empty frame captured, retrying..."),
             Err(_) => {
                 eprintln!("Camera error, attempting to reconnect...");
-                cam = setup_camera(); // Try to reinitialize camera if it fails
+                let reconnect_cfg = config.lock().unwrap().clone();
+                cam = setup_camera(&source, &reconnect_cfg); // Try to reinitialize camera if it fails
+                applied_width = reconnect_cfg.width;
+                applied_height = reconnect_cfg.height;
+                stats.camera_reconnects.fetch_add(1, Ordering::Relaxed);
             }
         }
 
-        // Approximate delay to maintain 30 FPS
-        std::thread::sleep(Duration::from_millis(33));
+        // Approximate delay to maintain the configured FPS
+        let frame_budget_ms = 1000u64.checked_div(cfg.fps as u64).unwrap_or(33).max(1);
+        std::thread::sleep(Duration::from_millis(frame_budget_ms));
     }
 }
 
@@ -99,16 +167,16 @@ fn try_send(socket: &Socket, data: &opencv::core::Vector<u8>) -> Result<(), ()>
     }
 }
 
-// Utility function to set up and initialize the camera with retries
-fn setup_camera() -> videoio::VideoCapture {
+// Utility function to set up and initialize the capture source with retries
+fn setup_camera(source: &CaptureSource, config: &CaptureConfig) -> videoio::VideoCapture {
     loop {
-        match videoio::VideoCapture::new(0, videoio::CAP_ANY) {
+        match source.open() {
             Ok(mut cam) => {
-                cam.set(videoio::CAP_PROP_FRAME_WIDTH, 640.0).ok();
-                cam.set(videoio::CAP_PROP_FRAME_HEIGHT, 480.0).ok();
+                cam.set(videoio::CAP_PROP_FRAME_WIDTH, config.width as f64).ok();
+                cam.set(videoio::CAP_PROP_FRAME_HEIGHT, config.height as f64).ok();
                 return cam;
             }
-            Err(_) => eprintln!("Failed to initialize camera, retrying..."),
+            Err(_) => eprintln!("Failed to initialize capture source, retrying..."),
         }
         std::thread::sleep(Duration::from_secs(1)); // Retry every second
     }
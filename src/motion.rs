@@ -0,0 +1,172 @@
+use opencv::core::{Mat, Size};
+use opencv::imgproc;
+use opencv::prelude::*;
+use std::env;
+
+/// Frames are downscaled to this fixed size before diffing, to keep the
+/// motion check cheap regardless of the camera's native resolution.
+const GATE_SIZE: i32 = 64;
+
+/// Cheap inter-frame difference gate: skips forwarding frames that look
+/// the same as the last one, so a slow-changing scene doesn't spend
+/// ZeroMQ bandwidth and GPU time on near-identical frames.
+pub struct MotionGate {
+    threshold: f64,
+    keyframe_interval: u64,
+    previous: Option<Mat>,
+    frames_since_keyframe: u64,
+}
+
+impl MotionGate {
+    pub fn from_env() -> Self {
+        MotionGate {
+            threshold: env::var("MOTION_THRESHOLD")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(0.02),
+            keyframe_interval: env::var("KEYFRAME_INTERVAL")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(150),
+            previous: None,
+            frames_since_keyframe: 0,
+        }
+    }
+
+    /// Returns `true` if `frame` should be forwarded: either it differs
+    /// enough from the previous frame (forwarded or not), or a keyframe is
+    /// due so slow drift can't suppress emission forever.
+    pub fn should_emit(&mut self, frame: &Mat) -> opencv::Result<bool> {
+        let downscaled = downscale_gray(frame)?;
+        self.frames_since_keyframe += 1;
+
+        let changed = match &self.previous {
+            Some(previous) => mean_abs_diff(previous, &downscaled)? > self.threshold,
+            None => true,
+        };
+        let force_keyframe = keyframe_due(self.frames_since_keyframe, self.keyframe_interval);
+
+        self.previous = Some(downscaled);
+
+        if changed || force_keyframe {
+            self.frames_since_keyframe = 0;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+/// Whether a keyframe is due: `frames_since_keyframe` has reached
+/// `keyframe_interval`, so slow drift can't suppress emission forever.
+fn keyframe_due(frames_since_keyframe: u64, keyframe_interval: u64) -> bool {
+    frames_since_keyframe >= keyframe_interval
+}
+
+fn downscale_gray(frame: &Mat) -> opencv::Result<Mat> {
+    let mut gray = Mat::default();
+    imgproc::cvt_color(frame, &mut gray, imgproc::COLOR_BGR2GRAY, 0)?;
+
+    let mut small = Mat::default();
+    imgproc::resize(
+        &gray,
+        &mut small,
+        Size::new(GATE_SIZE, GATE_SIZE),
+        0.0,
+        0.0,
+        imgproc::INTER_LINEAR,
+    )?;
+    Ok(small)
+}
+
+/// Mean absolute pixel difference between two `GATE_SIZE`x`GATE_SIZE`
+/// grayscale frames, normalized to a 0-1 score.
+fn mean_abs_diff(previous: &Mat, current: &Mat) -> opencv::Result<f64> {
+    let mut diff = Mat::default();
+    opencv::core::absdiff(previous, current, &mut diff)?;
+    let sum = opencv::core::sum_elems(&diff)?;
+    let pixel_count = (GATE_SIZE * GATE_SIZE) as f64;
+    Ok(sum[0] / (pixel_count * 255.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opencv::core::{Scalar, CV_8UC3};
+
+    #[test]
+    fn keyframe_not_due_before_interval_elapses() {
+        assert!(!keyframe_due(149, 150));
+    }
+
+    #[test]
+    fn keyframe_due_once_interval_elapses() {
+        assert!(keyframe_due(150, 150));
+    }
+
+    /// Solid-color BGR frame, mirroring how `preprocess.rs`'s tests build
+    /// sample input without a real camera.
+    fn solid_frame(width: i32, height: i32, bgr: (u8, u8, u8)) -> Mat {
+        Mat::new_rows_cols_with_default(
+            height,
+            width,
+            CV_8UC3,
+            Scalar::new(bgr.0 as f64, bgr.1 as f64, bgr.2 as f64, 0.0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn first_frame_is_always_emitted() {
+        let mut gate = MotionGate {
+            threshold: 0.02,
+            keyframe_interval: 150,
+            previous: None,
+            frames_since_keyframe: 0,
+        };
+        let frame = solid_frame(320, 240, (0, 0, 0));
+        assert!(gate.should_emit(&frame).unwrap());
+    }
+
+    #[test]
+    fn identical_frames_are_suppressed_below_threshold() {
+        let mut gate = MotionGate {
+            threshold: 0.02,
+            keyframe_interval: 150,
+            previous: None,
+            frames_since_keyframe: 0,
+        };
+        let frame = solid_frame(320, 240, (10, 10, 10));
+        assert!(gate.should_emit(&frame).unwrap());
+        assert!(!gate.should_emit(&frame).unwrap());
+    }
+
+    #[test]
+    fn a_large_change_is_emitted_even_within_threshold_window() {
+        let mut gate = MotionGate {
+            threshold: 0.02,
+            keyframe_interval: 150,
+            previous: None,
+            frames_since_keyframe: 0,
+        };
+        let dark = solid_frame(320, 240, (0, 0, 0));
+        let bright = solid_frame(320, 240, (255, 255, 255));
+        assert!(gate.should_emit(&dark).unwrap());
+        assert!(gate.should_emit(&bright).unwrap());
+    }
+
+    #[test]
+    fn keyframe_interval_forces_emission_despite_no_change() {
+        let mut gate = MotionGate {
+            threshold: 0.02,
+            keyframe_interval: 2,
+            previous: None,
+            frames_since_keyframe: 0,
+        };
+        let frame = solid_frame(320, 240, (10, 10, 10));
+
+        assert!(gate.should_emit(&frame).unwrap()); // first frame
+        assert!(!gate.should_emit(&frame).unwrap()); // unchanged, suppressed
+        assert!(gate.should_emit(&frame).unwrap()); // unchanged, but keyframe due
+    }
+}
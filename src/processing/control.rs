@@ -0,0 +1,94 @@
+use std::env;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+use zmq::Context;
+
+use crate::worker_pool::WorkerPoolStats;
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "command")]
+enum ControlRequest {
+    Ping,
+    GetStats,
+    Shutdown,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "status")]
+enum ControlResponse {
+    Ok,
+    Stats {
+        frames_processed: u64,
+        frames_failed: u64,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// Spawn the REQ/REP control-plane thread and return immediately; the
+/// socket runs on its own thread for the lifetime of the process.
+pub fn spawn_control_thread(context: &Context, stats: Arc<WorkerPoolStats>) {
+    let context = context.clone();
+    thread::spawn(move || control_loop(&context, &stats));
+}
+
+fn control_loop(context: &Context, stats: &Arc<WorkerPoolStats>) {
+    // Defaults to a different port than the capture daemon's control socket
+    // (tcp://*:5556) so both daemons can bind their defaults on the same
+    // host without a collision.
+    let address = env::var("CONTROL_ADDRESS").unwrap_or_else(|_| "tcp://*:5558".to_string());
+
+    let socket = match context.socket(zmq::REP) {
+        Ok(socket) => socket,
+        Err(e) => {
+            eprintln!("Failed to create control socket: {:?}", e);
+            return;
+        }
+    };
+    if let Err(e) = socket.bind(&address) {
+        eprintln!("Failed to bind control socket to {}: {:?}", address, e);
+        return;
+    }
+
+    loop {
+        let request = match socket.recv_string(0) {
+            Ok(Ok(request)) => request,
+            _ => {
+                eprintln!("Failed to receive control request");
+                continue;
+            }
+        };
+
+        let response = handle_request(&request, stats);
+        let payload = serde_json::to_string(&response).unwrap_or_else(|_| {
+            "{\"status\":\"Error\",\"message\":\"failed to encode response\"}".to_string()
+        });
+        if let Err(e) = socket.send(&payload, 0) {
+            eprintln!("Failed to send control response: {:?}", e);
+        }
+    }
+}
+
+fn handle_request(raw: &str, stats: &Arc<WorkerPoolStats>) -> ControlResponse {
+    let request: ControlRequest = match serde_json::from_str(raw) {
+        Ok(request) => request,
+        Err(e) => {
+            return ControlResponse::Error {
+                message: format!("invalid request: {}", e),
+            }
+        }
+    };
+
+    match request {
+        ControlRequest::Ping => ControlResponse::Ok,
+        ControlRequest::GetStats => ControlResponse::Stats {
+            frames_processed: stats.frames_processed.load(Ordering::Relaxed),
+            frames_failed: stats.frames_failed.load(Ordering::Relaxed),
+        },
+        ControlRequest::Shutdown => std::process::exit(0),
+    }
+}
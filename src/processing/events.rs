@@ -0,0 +1,124 @@
+use std::env;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use zmq::Context;
+
+/// Structured detection result published on the results bus for each
+/// processed frame.
+#[derive(Serialize)]
+struct DetectionEvent {
+    timestamp: u64,
+    frame_id: u64,
+    outputs: Vec<Vec<f32>>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "event")]
+enum SessionEvent {
+    RecordingStarted { timestamp: u64 },
+    NoDetection { seconds: u64, timestamp: u64 },
+}
+
+/// ZeroMQ PUB socket that publishes structured detection results so
+/// downstream subscribers can act on them, plus "session started" /
+/// "no detection for N seconds" events.
+pub struct EventBus {
+    socket: Mutex<zmq::Socket>,
+    last_detection: Mutex<Instant>,
+    no_detection_threshold: Duration,
+}
+
+impl EventBus {
+    pub fn new(context: &Context) -> Self {
+        let address = env::var("EVENTS_ADDRESS").unwrap_or_else(|_| "tcp://*:5557".to_string());
+        let socket = setup_socket(context, &address);
+
+        let threshold_secs = env::var("NO_DETECTION_SECONDS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(30);
+
+        let bus = EventBus {
+            socket: Mutex::new(socket),
+            last_detection: Mutex::new(Instant::now()),
+            no_detection_threshold: Duration::from_secs(threshold_secs),
+        };
+        bus.publish_session_event(&SessionEvent::RecordingStarted {
+            timestamp: unix_now(),
+        });
+        bus
+    }
+
+    /// Publish a detection result and mark the session as active.
+    pub fn publish_detection(&self, frame_id: u64, outputs: &[Vec<f32>]) {
+        *self.last_detection.lock().unwrap() = Instant::now();
+        let event = DetectionEvent {
+            timestamp: unix_now(),
+            frame_id,
+            outputs: outputs.to_vec(),
+        };
+        self.publish("detections", &event);
+    }
+
+    /// Emit a "no detection for N seconds" event if the quiet period has
+    /// been exceeded since the last detection. Intended to be polled
+    /// periodically from a background thread.
+    pub fn check_no_detection(&self) {
+        let elapsed = self.last_detection.lock().unwrap().elapsed();
+        if elapsed < self.no_detection_threshold {
+            return;
+        }
+        self.publish_session_event(&SessionEvent::NoDetection {
+            seconds: elapsed.as_secs(),
+            timestamp: unix_now(),
+        });
+        // Reset so the event doesn't fire on every poll while still quiet.
+        *self.last_detection.lock().unwrap() = Instant::now();
+    }
+
+    fn publish_session_event(&self, event: &SessionEvent) {
+        self.publish("session", event);
+    }
+
+    fn publish<T: Serialize>(&self, topic: &str, payload: &T) {
+        let Ok(json) = serde_json::to_string(payload) else {
+            return;
+        };
+        let message = format!("{topic} {json}");
+        match self.socket.lock() {
+            Ok(socket) => {
+                if let Err(e) = socket.send(&message, 0) {
+                    eprintln!("Failed to publish event: {:?}", e);
+                }
+            }
+            Err(e) => eprintln!("Results socket lock poisoned: {:?}", e),
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+// Utility function to set up a ZeroMQ PUB socket with retries, matching the
+// resilience of the other sockets in this daemon rather than crashing the
+// already-backgrounded process over a transient bind failure.
+fn setup_socket(context: &Context, address: &str) -> zmq::Socket {
+    loop {
+        match context.socket(zmq::PUB) {
+            Ok(socket) => {
+                if socket.bind(address).is_ok() {
+                    return socket;
+                }
+                eprintln!("Failed to bind results PUB socket to {}, retrying...", address);
+            }
+            Err(_) => eprintln!("Failed to create results PUB socket, retrying..."),
+        }
+        std::thread::sleep(Duration::from_secs(1));
+    }
+}
@@ -3,8 +3,17 @@ use zmq::{Context, Socket};
 use std::time::Duration;
 use std::env;
 use std::process::exit;
+use std::sync::Arc;
 use nix::unistd::{fork, ForkResult, setsid};
 
+mod control;
+mod events;
+mod preprocess;
+mod worker_pool;
+
+use events::EventBus;
+use preprocess::PreprocessConfig;
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Daemonize the process
     daemonize();
@@ -44,23 +53,46 @@ fn run_processing_loop() -> Result<(), Box<dyn std::error::Error>> {
     let socket = setup_socket(&context, &zmq_address)?;
 
     // Set up ONNX Runtime environment and load model with retries
-    let environment = Environment::builder()
-        .with_name("InferenceEnvironment")
-        .with_log_level(LoggingLevel::Warning)
-        .build()
-        .expect("Failed to create ONNX Runtime environment");
+    let environment = Arc::new(
+        Environment::builder()
+            .with_name("InferenceEnvironment")
+            .with_log_level(LoggingLevel::Warning)
+            .build()
+            .expect("Failed to create ONNX Runtime environment"),
+    );
+
+    let preprocess_config = PreprocessConfig::from_env();
+
+    // Results bus: publishes structured detection results plus
+    // session-level "recording started" / "no detection" events.
+    let events = Arc::new(EventBus::new(&context));
+
+    // Fan frames out to a pool of inference worker threads so a slow model
+    // can't starve the ingress socket; each worker owns its own Session.
+    let pool = worker_pool::WorkerPool::new(
+        Arc::clone(&environment),
+        model_path.clone(),
+        preprocess_config,
+        Arc::clone(&events),
+    );
 
-    let session = setup_model(&environment, &model_path)?;
+    // Expose liveness and throughput over the control socket.
+    control::spawn_control_thread(&context, pool.stats());
+
+    // Periodically check whether the feed has gone quiet long enough to
+    // warrant a "no detection for N seconds" event.
+    {
+        let events = Arc::clone(&events);
+        std::thread::spawn(move || loop {
+            events.check_no_detection();
+            std::thread::sleep(Duration::from_secs(1));
+        });
+    }
 
     // Start processing loop
     loop {
         match socket.recv_bytes(0) {
-            Ok(frame_data) => {
-                match process_frame(&session, frame_data) {
-                    Ok(outputs) => println!("{:?}", outputs), // Process outputs here or send results back via ZeroMQ
-                    Err(e) => eprintln!("Error during inference: {:?}", e),
-                }
-            }
+            Ok(frame_data) => pool.submit(frame_data),
             Err(e) => {
                 eprintln!("Failed to receive frame: {:?}", e);
                 std::thread::sleep(Duration::from_secs(1)); // Retry after delay
@@ -87,7 +119,7 @@ fn setup_socket(context: &Context, address: &str) -> Result<Socket, zmq::Error>
     }
 }
 
-fn setup_model(environment: &Environment, model_path: &str) -> Result<Session, Box<dyn std::error::Error>> {
+pub(crate) fn setup_model(environment: &Environment, model_path: &str) -> Result<Session, Box<dyn std::error::Error>> {
     loop {
         match environment.new_session_builder()
             .and_then(|builder| builder.with_graph_optimization_level(GraphOptimizationLevel::Basic))
@@ -101,9 +133,13 @@ fn setup_model(environment: &Environment, model_path: &str) -> Result<Session, B
     }
 }
 
-fn process_frame(session: &Session, frame_data: Vec<u8>) -> Result<Vec<Array<f32, _>>, Box<dyn std::error::Error>> {
-    // Convert the frame into an input tensor
-    let input_tensor = Array::from_shape_vec((1, 3, 224, 224), frame_data)?; // Adjust shape as needed
+pub(crate) fn process_frame(
+    session: &Session,
+    frame_data: Vec<u8>,
+    preprocess_config: &preprocess::PreprocessConfig,
+) -> Result<Vec<Array<f32, _>>, Box<dyn std::error::Error>> {
+    // Decode the JPEG and turn it into a normalized (1, 3, H, W) input tensor.
+    let input_tensor = preprocess::preprocess(&frame_data, preprocess_config)?;
 
     // Run inference
     let outputs: Vec<Array<f32, _>> = session.run(vec![input_tensor.into_dyn()])?;
@@ -0,0 +1,149 @@
+use onnxruntime::ndarray::Array4;
+use opencv::core::{Size, Vec3b, Vector};
+use opencv::prelude::*;
+use opencv::{imgcodecs, imgproc};
+use std::env;
+
+/// ImageNet mean, used as the default per-channel normalization.
+const DEFAULT_MEAN: [f32; 3] = [0.485, 0.456, 0.406];
+/// ImageNet std, used as the default per-channel normalization.
+const DEFAULT_STD: [f32; 3] = [0.229, 0.224, 0.225];
+
+/// Model input shape and normalization parameters, read from the
+/// environment so different models work without recompilation.
+#[derive(Clone, Debug)]
+pub struct PreprocessConfig {
+    pub width: i32,
+    pub height: i32,
+    pub mean: [f32; 3],
+    pub std: [f32; 3],
+}
+
+impl Default for PreprocessConfig {
+    fn default() -> Self {
+        PreprocessConfig {
+            width: 224,
+            height: 224,
+            mean: DEFAULT_MEAN,
+            std: DEFAULT_STD,
+        }
+    }
+}
+
+impl PreprocessConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        PreprocessConfig {
+            width: env_i32("INPUT_WIDTH", defaults.width),
+            height: env_i32("INPUT_HEIGHT", defaults.height),
+            mean: env_channels("INPUT_MEAN", defaults.mean),
+            std: env_channels("INPUT_STD", defaults.std),
+        }
+    }
+}
+
+fn env_i32(key: &str, default: i32) -> i32 {
+    env::var(key)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+fn env_channels(key: &str, default: [f32; 3]) -> [f32; 3] {
+    let Ok(value) = env::var(key) else {
+        return default;
+    };
+    let parts: Vec<f32> = value
+        .split(',')
+        .filter_map(|part| part.trim().parse().ok())
+        .collect();
+    match parts[..] {
+        [r, g, b] => [r, g, b],
+        _ => default,
+    }
+}
+
+/// Decode a JPEG-encoded frame and turn it into a `(1, 3, H, W)` CHW tensor
+/// ready for inference: resize, BGR->RGB, scale to `[0, 1]`, then normalize
+/// per-channel.
+pub fn preprocess(frame_data: &[u8], config: &PreprocessConfig) -> opencv::Result<Array4<f32>> {
+    let buffer = Vector::<u8>::from_slice(frame_data);
+    let decoded = imgcodecs::imdecode(&buffer, imgcodecs::IMREAD_COLOR)?;
+
+    let mut resized = Mat::default();
+    imgproc::resize(
+        &decoded,
+        &mut resized,
+        Size::new(config.width, config.height),
+        0.0,
+        0.0,
+        imgproc::INTER_LINEAR,
+    )?;
+
+    let mut rgb = Mat::default();
+    imgproc::cvt_color(&resized, &mut rgb, imgproc::COLOR_BGR2RGB, 0)?;
+
+    let width = config.width as usize;
+    let height = config.height as usize;
+    let mut tensor = Array4::<f32>::zeros((1, 3, height, width));
+    for y in 0..height {
+        for x in 0..width {
+            let pixel = rgb.at_2d::<Vec3b>(y as i32, x as i32)?;
+            for c in 0..3 {
+                let scaled = pixel[c] as f32 / 255.0;
+                tensor[[0, c, y, x]] = (scaled - config.mean[c]) / config.std[c];
+            }
+        }
+    }
+
+    Ok(tensor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opencv::core::{Scalar, CV_8UC3};
+
+    /// Encodes a solid-color BGR frame as JPEG, mirroring what the capture
+    /// daemon sends over the wire.
+    fn sample_jpeg(width: i32, height: i32, bgr: (u8, u8, u8)) -> Vec<u8> {
+        let mat = Mat::new_rows_cols_with_default(
+            height,
+            width,
+            CV_8UC3,
+            Scalar::new(bgr.0 as f64, bgr.1 as f64, bgr.2 as f64, 0.0),
+        )
+        .unwrap();
+        let mut encoded = Vector::<u8>::new();
+        imgcodecs::imencode(".jpg", &mat, &mut encoded, &Vector::new()).unwrap();
+        encoded.to_vec()
+    }
+
+    #[test]
+    fn produces_expected_tensor_shape() {
+        let config = PreprocessConfig::default();
+        let jpeg = sample_jpeg(320, 240, (0, 0, 255));
+
+        let tensor = preprocess(&jpeg, &config).unwrap();
+
+        assert_eq!(tensor.shape(), &[1, 3, config.height as usize, config.width as usize]);
+    }
+
+    #[test]
+    fn normalizes_a_solid_red_frame() {
+        // JPEG is lossy, so allow a little slack around the expected value
+        // instead of asserting exact equality.
+        let config = PreprocessConfig::default();
+        let jpeg = sample_jpeg(64, 64, (0, 0, 255)); // BGR red
+
+        let tensor = preprocess(&jpeg, &config).unwrap();
+
+        let expected_red = (1.0 - config.mean[0]) / config.std[0];
+        let expected_green = (0.0 - config.mean[1]) / config.std[1];
+        let expected_blue = (0.0 - config.mean[2]) / config.std[2];
+
+        assert!((tensor[[0, 0, 0, 0]] - expected_red).abs() < 0.1);
+        assert!((tensor[[0, 1, 0, 0]] - expected_green).abs() < 0.1);
+        assert!((tensor[[0, 2, 0, 0]] - expected_blue).abs() < 0.1);
+    }
+}
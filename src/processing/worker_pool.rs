@@ -0,0 +1,231 @@
+use onnxruntime::environment::Environment;
+use std::collections::VecDeque;
+use std::env;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use crate::events::EventBus;
+use crate::preprocess::PreprocessConfig;
+use crate::{process_frame, setup_model};
+
+/// Slots per worker thread in the shared frame queue; once the queue holds
+/// this many frames per worker, the oldest one gets dropped to keep latency
+/// bounded.
+const QUEUE_DEPTH_PER_WORKER: usize = 4;
+
+/// A queued frame tagged with the sequential id it was assigned on ingress,
+/// so published detection events can reference which frame they came from.
+struct QueuedFrame {
+    id: u64,
+    data: Vec<u8>,
+}
+
+/// Bounded, drop-oldest queue shared by the worker threads. Acts as the
+/// in-process fan-out point for frames coming off the single ingress socket.
+struct FrameQueue {
+    inner: Mutex<VecDeque<QueuedFrame>>,
+    ready: Condvar,
+    capacity: usize,
+    next_frame_id: AtomicU64,
+}
+
+impl FrameQueue {
+    fn new(capacity: usize) -> Self {
+        FrameQueue {
+            inner: Mutex::new(VecDeque::with_capacity(capacity)),
+            ready: Condvar::new(),
+            capacity,
+            next_frame_id: AtomicU64::new(0),
+        }
+    }
+
+    fn push(&self, data: Vec<u8>) {
+        let id = self.next_frame_id.fetch_add(1, Ordering::Relaxed);
+        let mut queue = self.inner.lock().unwrap();
+        if queue.len() >= self.capacity {
+            // Drop the oldest frame rather than queue unboundedly, so a slow
+            // model can't let latency grow without limit.
+            queue.pop_front();
+        }
+        queue.push_back(QueuedFrame { id, data });
+        self.ready.notify_one();
+    }
+
+    fn pop(&self) -> QueuedFrame {
+        let mut queue = self.inner.lock().unwrap();
+        while queue.is_empty() {
+            queue = self.ready.wait(queue).unwrap();
+        }
+        queue.pop_front().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pop_returns_frames_in_push_order() {
+        let queue = FrameQueue::new(4);
+        queue.push(vec![1]);
+        queue.push(vec![2]);
+        queue.push(vec![3]);
+
+        assert_eq!(queue.pop().data, vec![1]);
+        assert_eq!(queue.pop().data, vec![2]);
+        assert_eq!(queue.pop().data, vec![3]);
+    }
+
+    #[test]
+    fn push_assigns_sequential_ids() {
+        let queue = FrameQueue::new(4);
+        queue.push(vec![1]);
+        queue.push(vec![2]);
+
+        let first = queue.pop();
+        let second = queue.pop();
+        assert_eq!(first.id, 0);
+        assert_eq!(second.id, 1);
+    }
+
+    #[test]
+    fn push_drops_the_oldest_frame_once_at_capacity() {
+        let queue = FrameQueue::new(2);
+        queue.push(vec![1]);
+        queue.push(vec![2]);
+        queue.push(vec![3]); // over capacity: frame 1 should be dropped
+
+        assert_eq!(queue.pop().data, vec![2]);
+        assert_eq!(queue.pop().data, vec![3]);
+    }
+
+    #[test]
+    fn push_drops_oldest_but_keeps_ids_monotonic() {
+        let queue = FrameQueue::new(1);
+        queue.push(vec![1]);
+        queue.push(vec![2]); // drops frame id 0
+
+        let remaining = queue.pop();
+        assert_eq!(remaining.id, 1);
+        assert_eq!(remaining.data, vec![2]);
+    }
+}
+
+/// A pool of inference worker threads, each owning its own ONNX `Session`
+/// since sessions aren't cheaply shareable across threads. Frames are handed
+/// to the pool from the single PULL socket and fair-queued out to whichever
+/// worker asks for one next.
+pub struct WorkerPool {
+    queue: Arc<FrameQueue>,
+    stats: Arc<WorkerPoolStats>,
+    _handles: Vec<thread::JoinHandle<()>>,
+}
+
+/// Running counters surfaced through the control socket's `GetStats`.
+#[derive(Default)]
+pub struct WorkerPoolStats {
+    pub frames_processed: AtomicU64,
+    pub frames_failed: AtomicU64,
+}
+
+impl WorkerPool {
+    pub fn new(
+        environment: Arc<Environment>,
+        model_path: String,
+        preprocess_config: PreprocessConfig,
+        events: Arc<EventBus>,
+    ) -> Self {
+        let worker_count = worker_thread_count();
+        let queue = Arc::new(FrameQueue::new(worker_count * QUEUE_DEPTH_PER_WORKER));
+        let stats = Arc::new(WorkerPoolStats::default());
+
+        let handles = (0..worker_count)
+            .map(|id| {
+                let queue = Arc::clone(&queue);
+                let environment = Arc::clone(&environment);
+                let model_path = model_path.clone();
+                let preprocess_config = preprocess_config.clone();
+                let stats = Arc::clone(&stats);
+                let events = Arc::clone(&events);
+                thread::spawn(move || {
+                    worker_loop(
+                        id,
+                        &queue,
+                        &environment,
+                        &model_path,
+                        &preprocess_config,
+                        &stats,
+                        &events,
+                    )
+                })
+            })
+            .collect();
+
+        WorkerPool {
+            queue,
+            stats,
+            _handles: handles,
+        }
+    }
+
+    /// Hand a frame off to the pool, dropping the oldest queued frame if full.
+    pub fn submit(&self, frame_data: Vec<u8>) {
+        self.queue.push(frame_data);
+    }
+
+    pub fn stats(&self) -> Arc<WorkerPoolStats> {
+        Arc::clone(&self.stats)
+    }
+}
+
+/// Number of inference worker threads to spawn: `WORKER_THREADS` overrides
+/// the detected core count.
+fn worker_thread_count() -> usize {
+    env::var("WORKER_THREADS")
+        .ok()
+        .and_then(|value| value.parse::<usize>().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        })
+}
+
+fn worker_loop(
+    id: usize,
+    queue: &FrameQueue,
+    environment: &Environment,
+    model_path: &str,
+    preprocess_config: &PreprocessConfig,
+    stats: &WorkerPoolStats,
+    events: &EventBus,
+) {
+    let session = match setup_model(environment, model_path) {
+        Ok(session) => session,
+        Err(e) => {
+            eprintln!("Worker {id}: failed to load model from {model_path}: {e:?}");
+            return;
+        }
+    };
+
+    loop {
+        let frame = queue.pop();
+        match process_frame(&session, frame.data, preprocess_config) {
+            Ok(outputs) => {
+                println!("Worker {id}: {:?}", outputs);
+                stats.frames_processed.fetch_add(1, Ordering::Relaxed);
+                let flattened: Vec<Vec<f32>> = outputs
+                    .iter()
+                    .map(|output| output.iter().copied().collect())
+                    .collect();
+                events.publish_detection(frame.id, &flattened);
+            }
+            Err(e) => {
+                eprintln!("Worker {id}: error during inference: {:?}", e);
+                stats.frames_failed.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
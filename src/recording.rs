@@ -0,0 +1,267 @@
+use opencv::core::{Mat, Size};
+use opencv::prelude::*;
+use opencv::videoio::{VideoWriter, VideoWriterTrait};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Abstracts wall-clock reads so segment boundaries and retention sweeps
+/// can be driven deterministically in tests.
+pub trait Clock {
+    fn now(&self) -> SystemTime;
+}
+
+pub struct RealClock;
+
+impl Clock for RealClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Where to record to and how to rotate/retain segments, read from the
+/// environment. Recording is disabled unless `RECORD_DIR` is set.
+pub struct RecordingConfig {
+    pub dir: Option<PathBuf>,
+    pub segment_duration: Duration,
+    pub retention_bytes: u64,
+}
+
+impl RecordingConfig {
+    pub fn from_env() -> Self {
+        RecordingConfig {
+            dir: env::var("RECORD_DIR").ok().map(PathBuf::from),
+            segment_duration: Duration::from_secs(env_u64("RECORD_SEGMENT_SECONDS", 300)),
+            retention_bytes: env_u64("RECORD_RETENTION_BYTES", 10 * 1024 * 1024 * 1024),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.dir.is_some()
+    }
+}
+
+fn env_u64(key: &str, default: u64) -> u64 {
+    env::var(key)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+/// A segment that has been rotated away from and is now just a file on
+/// disk, tracked so the retention sweep can evict the oldest ones without
+/// re-scanning the directory.
+struct SegmentInfo {
+    path: PathBuf,
+    size_bytes: u64,
+}
+
+/// Persists captured frames to rotating video segments, naming each by its
+/// wall-clock start time, and deletes the oldest segments once the total
+/// size of the finished segments it has written exceeds
+/// `RecordingConfig::retention_bytes` - a continuous ring buffer of footage.
+/// The segment currently being written, and files left in the directory by
+/// earlier runs, are not counted towards that total.
+pub struct Recorder<C: Clock> {
+    config: RecordingConfig,
+    clock: C,
+    writer: Option<VideoWriter>,
+    segment_started_at: Option<SystemTime>,
+    segment_path: Option<PathBuf>,
+    segments: Vec<SegmentInfo>,
+}
+
+impl<C: Clock> Recorder<C> {
+    pub fn new(config: RecordingConfig, clock: C) -> Self {
+        Recorder {
+            config,
+            clock,
+            writer: None,
+            segment_started_at: None,
+            segment_path: None,
+            segments: Vec::new(),
+        }
+    }
+
+    /// Writes `frame` to the current segment, rotating first if needed and
+    /// sweeping retention afterwards. No-op if recording isn't enabled.
+    ///
+    /// `frame_size` and `fps` are read from the live capture state on every
+    /// call (rather than captured once at startup) so a newly-opened
+    /// segment always matches what the camera is actually producing, even
+    /// if resolution or FPS changed live via the control socket since the
+    /// last rotation.
+    pub fn record_frame(&mut self, frame: &Mat, frame_size: Size, fps: f64) -> opencv::Result<()> {
+        if !self.config.enabled() {
+            return Ok(());
+        }
+
+        let now = self.clock.now();
+        if needs_rotation(now, self.segment_started_at, self.config.segment_duration) {
+            self.rotate(now, frame_size, fps)?;
+        }
+
+        if let Some(writer) = &mut self.writer {
+            writer.write(frame)?;
+        }
+
+        self.enforce_retention();
+        Ok(())
+    }
+
+    fn rotate(&mut self, now: SystemTime, frame_size: Size, fps: f64) -> opencv::Result<()> {
+        self.close_current_segment();
+
+        let dir = self
+            .config
+            .dir
+            .as_ref()
+            .expect("recording enabled implies dir is set");
+        fs::create_dir_all(dir).ok();
+
+        let path = dir.join(segment_filename(now));
+        let fourcc = VideoWriter::fourcc('m', 'p', '4', 'v')?;
+        let writer = VideoWriter::new(path.to_string_lossy().as_ref(), fourcc, fps, frame_size, true)?;
+
+        self.writer = Some(writer);
+        self.segment_started_at = Some(now);
+        self.segment_path = Some(path);
+        Ok(())
+    }
+
+    fn close_current_segment(&mut self) {
+        self.writer = None;
+        if let Some(path) = self.segment_path.take() {
+            let size_bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            self.segments.push(SegmentInfo { path, size_bytes });
+        }
+    }
+
+    fn enforce_retention(&mut self) {
+        for idx in segments_to_evict(&self.segments, self.config.retention_bytes).into_iter().rev() {
+            let segment = self.segments.remove(idx);
+            if let Err(e) = fs::remove_file(&segment.path) {
+                eprintln!("Failed to delete retired segment {:?}: {:?}", segment.path, e);
+            }
+        }
+    }
+}
+
+fn needs_rotation(now: SystemTime, segment_started_at: Option<SystemTime>, segment_duration: Duration) -> bool {
+    match segment_started_at {
+        None => true,
+        Some(started) => now.duration_since(started).unwrap_or(Duration::ZERO) >= segment_duration,
+    }
+}
+
+fn segment_filename(now: SystemTime) -> String {
+    let epoch_secs = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    format!("segment-{epoch_secs}.mp4")
+}
+
+/// Returns the indices (oldest-first) of `segments` to delete so that the
+/// remaining total is at or under `cap_bytes`.
+fn segments_to_evict(segments: &[SegmentInfo], cap_bytes: u64) -> Vec<usize> {
+    let mut total: u64 = segments.iter().map(|s| s.size_bytes).sum();
+    let mut evict = Vec::new();
+    for (idx, segment) in segments.iter().enumerate() {
+        if total <= cap_bytes {
+            break;
+        }
+        total = total.saturating_sub(segment.size_bytes);
+        evict.push(idx);
+    }
+    evict
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    struct MockClock {
+        current: Cell<SystemTime>,
+    }
+
+    impl MockClock {
+        fn new(start: SystemTime) -> Self {
+            MockClock {
+                current: Cell::new(start),
+            }
+        }
+
+        fn advance(&self, by: Duration) {
+            self.current.set(self.current.get() + by);
+        }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> SystemTime {
+            self.current.get()
+        }
+    }
+
+    #[test]
+    fn rotates_on_first_frame() {
+        assert!(needs_rotation(UNIX_EPOCH, None, Duration::from_secs(300)));
+    }
+
+    #[test]
+    fn does_not_rotate_before_segment_duration_elapses() {
+        let started = UNIX_EPOCH;
+        let now = started + Duration::from_secs(299);
+        assert!(!needs_rotation(now, Some(started), Duration::from_secs(300)));
+    }
+
+    #[test]
+    fn rotates_once_segment_duration_elapses() {
+        let started = UNIX_EPOCH;
+        let now = started + Duration::from_secs(300);
+        assert!(needs_rotation(now, Some(started), Duration::from_secs(300)));
+    }
+
+    #[test]
+    fn mock_clock_drives_rotation_deterministically() {
+        let clock = MockClock::new(UNIX_EPOCH);
+        let segment_duration = Duration::from_secs(60);
+
+        assert!(needs_rotation(clock.now(), None, segment_duration));
+        let started = clock.now();
+
+        clock.advance(Duration::from_secs(30));
+        assert!(!needs_rotation(clock.now(), Some(started), segment_duration));
+
+        clock.advance(Duration::from_secs(30));
+        assert!(needs_rotation(clock.now(), Some(started), segment_duration));
+    }
+
+    fn segment(name: &str, size_bytes: u64) -> SegmentInfo {
+        SegmentInfo {
+            path: PathBuf::from(name),
+            size_bytes,
+        }
+    }
+
+    #[test]
+    fn retention_keeps_everything_under_the_cap() {
+        let segments = vec![segment("a", 10), segment("b", 10)];
+        assert!(segments_to_evict(&segments, 100).is_empty());
+    }
+
+    #[test]
+    fn retention_evicts_oldest_segments_first() {
+        let segments = vec![segment("a", 50), segment("b", 50), segment("c", 50)];
+        let evict = segments_to_evict(&segments, 80);
+        // Oldest ("a") must go first; evicting it alone drops total to 100,
+        // still over the cap, so the next-oldest ("b") goes too.
+        assert_eq!(evict, vec![0, 1]);
+    }
+
+    #[test]
+    fn retention_stops_once_under_the_cap() {
+        let segments = vec![segment("a", 50), segment("b", 50), segment("c", 50)];
+        let evict = segments_to_evict(&segments, 120);
+        assert_eq!(evict, vec![0]);
+    }
+}